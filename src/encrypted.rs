@@ -0,0 +1,151 @@
+//! Encrypt secrets at rest in memory between accesses
+//!
+//! [`EncryptedSecret`] keeps its plaintext encrypted whenever it isn't actively being
+//! read, shrinking the window in which cleartext key material exists in RAM. At
+//! construction it generates a random symmetric key and nonce, encrypts the incoming
+//! bytes, and stores only the ciphertext plus key/nonce; [`expose`](EncryptedSecret::expose)
+//! decrypts into a temporary zeroized-on-drop buffer, runs the caller's closure, then
+//! wipes the buffer before returning.
+//!
+//! # Honest threat model
+//!
+//! The key lives in the same address space as the ciphertext. This does **not**
+//! defeat a live debugger or a process with arbitrary memory-read access attached at
+//! the wrong moment — it raises the bar against casual memory scraping, swap/core-dump
+//! inspection, and scans of a serialized process image, by shrinking the amount of
+//! time and the amount of memory that ever holds cleartext.
+//!
+//! Requires the `memory-encryption` feature.
+
+use std::marker::PhantomData;
+use zeroize::{Zeroize, Zeroizing};
+
+/// Pluggable AEAD backend for [`EncryptedSecret`]
+///
+/// Implement this to swap in a different cipher; the crate ships
+/// [`ChaCha20Poly1305Backend`] as the default.
+pub trait CipherBackend {
+    /// Size in bytes of the symmetric key this backend expects
+    const KEY_LEN: usize;
+    /// Size in bytes of the nonce this backend expects
+    const NONCE_LEN: usize;
+
+    /// Encrypt `plaintext` under `key`/`nonce`
+    fn encrypt(key: &[u8], nonce: &[u8], plaintext: &[u8]) -> Vec<u8>;
+
+    /// Decrypt `ciphertext` under `key`/`nonce`
+    fn decrypt(key: &[u8], nonce: &[u8], ciphertext: &[u8]) -> Vec<u8>;
+}
+
+/// Default [`CipherBackend`]: ChaCha20-Poly1305
+pub struct ChaCha20Poly1305Backend;
+
+impl CipherBackend for ChaCha20Poly1305Backend {
+    const KEY_LEN: usize = 32;
+    const NONCE_LEN: usize = 12;
+
+    fn encrypt(key: &[u8], nonce: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        use chacha20poly1305::{aead::Aead, ChaCha20Poly1305, Key, KeyInit, Nonce};
+
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+        cipher
+            .encrypt(Nonce::from_slice(nonce), plaintext)
+            .expect("EncryptedSecret: encryption failure")
+    }
+
+    fn decrypt(key: &[u8], nonce: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+        use chacha20poly1305::{aead::Aead, ChaCha20Poly1305, Key, KeyInit, Nonce};
+
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+        cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .expect("EncryptedSecret: decryption failure (ciphertext or key corrupted)")
+    }
+}
+
+/// Secret bytes that stay encrypted in memory except during [`expose`](EncryptedSecret::expose)
+///
+/// See the [module docs](self) for what this does and does not protect against.
+pub struct EncryptedSecret<C: CipherBackend = ChaCha20Poly1305Backend> {
+    ciphertext: Vec<u8>,
+    key: Vec<u8>,
+    nonce: Vec<u8>,
+    _backend: PhantomData<C>,
+}
+
+impl<C: CipherBackend> EncryptedSecret<C> {
+    /// Encrypt `plaintext` under a freshly generated key/nonce and zeroize the source
+    pub fn new(mut plaintext: Vec<u8>) -> Self {
+        use rand::RngCore;
+
+        let mut key = vec![0u8; C::KEY_LEN];
+        let mut nonce = vec![0u8; C::NONCE_LEN];
+        rand::rngs::OsRng.fill_bytes(&mut key);
+        rand::rngs::OsRng.fill_bytes(&mut nonce);
+
+        let ciphertext = C::encrypt(&key, &nonce, &plaintext);
+        plaintext.zeroize();
+
+        Self {
+            ciphertext,
+            key,
+            nonce,
+            _backend: PhantomData,
+        }
+    }
+
+    /// Decrypt the secret into a temporary buffer, expose it to `f`, then wipe the
+    /// buffer before returning
+    ///
+    /// The buffer is held in a [`Zeroizing`] guard, so it is wiped even if `f` panics,
+    /// not just on a normal return.
+    ///
+    /// # Security Warning
+    ///
+    /// Do not output this value to logs or include it in error messages.
+    /// Use this method carefully and only when necessary.
+    pub fn expose<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&[u8]) -> R,
+    {
+        let plaintext = Zeroizing::new(C::decrypt(&self.key, &self.nonce, &self.ciphertext));
+        f(&plaintext)
+    }
+}
+
+impl<C: CipherBackend> Drop for EncryptedSecret<C> {
+    fn drop(&mut self) {
+        self.ciphertext.zeroize();
+        self.key.zeroize();
+        self.nonce.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that the secret round-trips through encryption/decryption
+    #[test]
+    fn test_encrypted_secret_roundtrip() {
+        let secret = EncryptedSecret::<ChaCha20Poly1305Backend>::new(vec![0x01, 0x02, 0x03]);
+        secret.expose(|b| assert_eq!(b, &[0x01, 0x02, 0x03]));
+    }
+
+    /// Verify that the stored ciphertext does not equal the plaintext
+    #[test]
+    fn test_encrypted_secret_stores_ciphertext_not_plaintext() {
+        let plaintext = vec![0x41; 16];
+        let secret = EncryptedSecret::<ChaCha20Poly1305Backend>::new(plaintext.clone());
+        assert_ne!(secret.ciphertext, plaintext);
+    }
+
+    /// Verify that a panic inside the closure unwinds cleanly (the decrypted buffer
+    /// is held in a `Zeroizing` guard, so it is wiped on unwind rather than leaked)
+    #[test]
+    #[should_panic(expected = "boom")]
+    fn test_encrypted_secret_expose_propagates_panic() {
+        let secret = EncryptedSecret::<ChaCha20Poly1305Backend>::new(vec![0x01, 0x02, 0x03]);
+        secret.expose(|_| panic!("boom"));
+    }
+}
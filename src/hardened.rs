@@ -0,0 +1,281 @@
+//! Hardened, page-aligned backing storage for secret bytes
+//!
+//! [`HardenedBytes`] is an alternate backing store for secret byte data: instead of an
+//! ordinary `Vec<u8>` (which the allocator may relocate, swap to disk, or include in a
+//! core dump), it allocates a dedicated, page-aligned memory region with:
+//!
+//! - A `PROT_NONE` guard page immediately before and after the data region, to trap
+//!   buffer overflow/underflow with a segfault rather than silent corruption.
+//! - A random canary word written just before the data, verified on Drop; if it was
+//!   modified, the process aborts instead of continuing with possibly-corrupted state.
+//! - `mlock(2)` on the data page, to keep it out of swap.
+//! - `mprotect(2)` to `PROT_NONE` while there is no outstanding borrow, flipping to
+//!   `PROT_READ` for [`expose`](HardenedBytes::expose) and `PROT_WRITE` for
+//!   [`expose_mut`](HardenedBytes::expose_mut).
+//!
+//! This is Unix-only and requires the `mlock` feature; portable/no-OS builds should
+//! keep using [`SecretBytes`](crate::SecretBytes), which masks Debug/Display the same
+//! way but has none of the OS-level protections. Unlike `SecretBytes::expose`, which
+//! returns a reference, [`expose`](HardenedBytes::expose) passes the bytes to a
+//! closure so the region can be relocked once the closure returns.
+
+use std::fmt;
+use std::ptr::NonNull;
+use zeroize::Zeroize;
+
+/// Number of outstanding borrows: `0` means no borrow (region is `PROT_NONE`), `>0` means
+/// shared `PROT_READ` borrows, and [`WRITE_BORROW`] means a single `PROT_WRITE`
+/// borrow is live.
+type BorrowState = isize;
+
+const WRITE_BORROW: BorrowState = -1;
+
+/// Secret bytes backed by a guarded, `mlock`ed, page-aligned allocation
+///
+/// See the [module docs](self) for the protections this provides.
+pub struct HardenedBytes {
+    data: NonNull<u8>,
+    len: usize,
+    page_size: usize,
+    canary: u64,
+    canary_ptr: NonNull<u64>,
+    borrows: std::cell::Cell<BorrowState>,
+}
+
+// The data is only ever accessed through `expose`/`expose_mut`, which take `&self`/
+// `&mut self`, so there is no aliasing hazard in sending the allocation across threads.
+unsafe impl Send for HardenedBytes {}
+
+impl HardenedBytes {
+    /// Copy `secret` into a new hardened, guarded allocation and zeroize the source
+    pub fn new(mut secret: Vec<u8>) -> Self {
+        use zeroize::Zeroize;
+
+        let hardened = unsafe { Self::allocate(&secret) };
+        secret.zeroize();
+        hardened
+    }
+
+    unsafe fn allocate(secret: &[u8]) -> Self {
+        let page_size = page_size();
+        // The canary word lives inside the same region, immediately before the data,
+        // so it must be accounted for when sizing the data pages or the copy below
+        // can spill into the trailing guard page.
+        let data_pages = (secret.len() + std::mem::size_of::<u64>())
+            .max(1)
+            .div_ceil(page_size);
+        // [guard page][canary + data pages][guard page]
+        let total_len = page_size * (data_pages + 2);
+
+        let map = libc::mmap(
+            std::ptr::null_mut(),
+            total_len,
+            libc::PROT_NONE,
+            libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+            -1,
+            0,
+        );
+        assert_ne!(map, libc::MAP_FAILED, "HardenedBytes: mmap failed");
+
+        let data_region = (map as *mut u8).add(page_size);
+        let region_len = page_size * data_pages;
+        assert_eq!(
+            libc::mprotect(
+                data_region as *mut libc::c_void,
+                region_len,
+                libc::PROT_READ | libc::PROT_WRITE,
+            ),
+            0,
+            "HardenedBytes: mprotect(PROT_READ | PROT_WRITE) failed"
+        );
+        // mlock failing (e.g. RLIMIT_MEMLOCK) means the secret can be swapped to disk
+        // despite the type's name, so treat it as fatal rather than degrading silently.
+        assert_eq!(
+            libc::mlock(data_region as *const libc::c_void, region_len),
+            0,
+            "HardenedBytes: mlock failed (RLIMIT_MEMLOCK too low?)"
+        );
+
+        let canary: u64 = generate_canary();
+        let canary_ptr = data_region as *mut u64;
+        canary_ptr.write(canary);
+
+        let data_ptr = data_region.add(std::mem::size_of::<u64>());
+        std::ptr::copy_nonoverlapping(secret.as_ptr(), data_ptr, secret.len());
+
+        // No outstanding borrow yet: lock the region down.
+        assert_eq!(
+            libc::mprotect(data_region as *mut libc::c_void, region_len, libc::PROT_NONE),
+            0,
+            "HardenedBytes: mprotect(PROT_NONE) failed"
+        );
+
+        Self {
+            data: NonNull::new(data_ptr).expect("mmap returned null"),
+            len: secret.len(),
+            page_size,
+            canary,
+            canary_ptr: NonNull::new(canary_ptr).expect("mmap returned null"),
+            borrows: std::cell::Cell::new(0),
+        }
+    }
+
+    fn data_pages(&self) -> usize {
+        (self.len + std::mem::size_of::<u64>())
+            .max(1)
+            .div_ceil(self.page_size)
+    }
+
+    fn region_ptr(&self) -> *mut libc::c_void {
+        self.canary_ptr.as_ptr() as *mut libc::c_void
+    }
+
+    /// Expose the secret bytes to `f`, unlocking the region for the duration of the
+    /// call and relocking it to `PROT_NONE` afterward.
+    ///
+    /// # Security Warning
+    ///
+    /// Do not output this value to logs or include it in error messages.
+    /// Use this method carefully and only when necessary.
+    pub fn expose<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&[u8]) -> R,
+    {
+        let region_len = self.page_size * self.data_pages();
+        unsafe { libc::mprotect(self.region_ptr(), region_len, libc::PROT_READ) };
+        self.borrows.set(self.borrows.get() + 1);
+
+        self.verify_canary();
+        let slice = unsafe { std::slice::from_raw_parts(self.data.as_ptr(), self.len) };
+        let result = f(slice);
+
+        self.borrows.set(self.borrows.get() - 1);
+        if self.borrows.get() == 0 {
+            unsafe { libc::mprotect(self.region_ptr(), region_len, libc::PROT_NONE) };
+        }
+        result
+    }
+
+    /// Expose the secret bytes mutably to `f`, unlocking the region for write access
+    /// for the duration of the call and relocking it to `PROT_NONE` afterward.
+    ///
+    /// # Security Warning
+    ///
+    /// Do not output this value to logs or include it in error messages.
+    /// Use this method carefully and only when necessary.
+    pub fn expose_mut<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut [u8]) -> R,
+    {
+        let region_len = self.page_size * self.data_pages();
+        unsafe { libc::mprotect(self.region_ptr(), region_len, libc::PROT_WRITE) };
+        self.borrows.set(WRITE_BORROW);
+
+        self.verify_canary();
+        let slice = unsafe { std::slice::from_raw_parts_mut(self.data.as_ptr(), self.len) };
+        let result = f(slice);
+
+        self.borrows.set(0);
+        unsafe { libc::mprotect(self.region_ptr(), region_len, libc::PROT_NONE) };
+        result
+    }
+
+    fn verify_canary(&self) {
+        let current = unsafe { self.canary_ptr.as_ptr().read() };
+        if current != self.canary {
+            // The canary was overwritten, meaning something wrote out of bounds into
+            // the guarded region. Continuing would operate on possibly-corrupted
+            // secret material, so abort immediately rather than return.
+            std::process::abort();
+        }
+    }
+}
+
+impl fmt::Debug for HardenedBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "HardenedBytes(***)")
+    }
+}
+
+impl fmt::Display for HardenedBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "***")
+    }
+}
+
+impl Drop for HardenedBytes {
+    fn drop(&mut self) {
+        let region_len = self.page_size * self.data_pages();
+        let total_len = self.page_size * (self.data_pages() + 2);
+        let map_start = unsafe { self.canary_ptr.as_ptr().cast::<u8>().sub(self.page_size) };
+
+        unsafe {
+            libc::mprotect(
+                self.region_ptr(),
+                region_len,
+                libc::PROT_READ | libc::PROT_WRITE,
+            );
+        }
+
+        // Verify before wiping: the canary is otherwise lost to the zeroize below.
+        self.verify_canary();
+
+        unsafe {
+            let zero_start = self.canary_ptr.as_ptr() as *mut u8;
+            // A plain memset is fair game for the optimizer to elide since nothing
+            // reads the region again before it's unmapped; zeroize's volatile writes
+            // are not, matching how every other type in this crate wipes secrets.
+            std::slice::from_raw_parts_mut(zero_start, region_len).zeroize();
+            libc::munlock(self.region_ptr(), region_len);
+            libc::munmap(map_start as *mut libc::c_void, total_len);
+        }
+    }
+}
+
+fn page_size() -> usize {
+    unsafe { libc::sysconf(libc::_SC_PAGESIZE) as usize }
+}
+
+fn generate_canary() -> u64 {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+
+    RandomState::new().build_hasher().finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that the secret round-trips through the hardened allocation
+    #[test]
+    fn test_hardened_bytes_roundtrip() {
+        let secret = HardenedBytes::new(vec![0x01, 0x02, 0x03, 0x04]);
+        secret.expose(|b| assert_eq!(b, &[0x01, 0x02, 0x03, 0x04]));
+    }
+
+    /// Verify that expose_mut can modify the secret in place
+    #[test]
+    fn test_hardened_bytes_expose_mut() {
+        let mut secret = HardenedBytes::new(vec![0x00, 0x00]);
+        secret.expose_mut(|b| b.copy_from_slice(&[0xAB, 0xCD]));
+        secret.expose(|b| assert_eq!(b, &[0xAB, 0xCD]));
+    }
+
+    /// Verify that Debug/Display mask the secret
+    #[test]
+    fn test_hardened_bytes_masked() {
+        let secret = HardenedBytes::new(vec![0x01]);
+        assert_eq!(format!("{:?}", secret), "HardenedBytes(***)");
+        assert_eq!(format!("{}", secret), "***");
+    }
+
+    /// Verify that a secret whose length is an exact multiple of the page size
+    /// doesn't spill the canary + data into the trailing guard page
+    #[test]
+    fn test_hardened_bytes_page_aligned_length() {
+        let len = page_size();
+        let secret = HardenedBytes::new(vec![0x42; len]);
+        secret.expose(|b| assert_eq!(b, vec![0x42; len].as_slice()));
+    }
+}
@@ -0,0 +1,41 @@
+//! # saferet
+//!
+//! Safe containers for secrets: byte strings, passwords, API keys, and other
+//! sensitive values that should be zeroized on drop and masked in Debug/Display
+//! output.
+//!
+//! See [`SecretBytes`] and [`SecretString`] for the ready-made containers, or
+//! [`Secret`] and [`define_secret!`] to wrap your own [`Zeroize`](zeroize::Zeroize)
+//! types the same way.
+//!
+//! # `no_std`
+//!
+//! [`Secret`], [`SecretBytes`], and [`SecretString`] only need `alloc` and work with
+//! the default `std` feature disabled. Optional features that depend on OS facilities
+//! (`mlock`, `memory-encryption`, the `std`-only [`FromStr`](std::str::FromStr) impl on
+//! [`SecretString`]) still require `std`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+mod macros;
+mod secret;
+mod secret_bytes;
+mod secret_string;
+#[cfg(feature = "typestate")]
+mod secret_n;
+#[cfg(all(feature = "mlock", unix))]
+mod hardened;
+#[cfg(feature = "memory-encryption")]
+mod encrypted;
+
+pub use secret::Secret;
+pub use secret_bytes::SecretBytes;
+pub use secret_string::SecretString;
+#[cfg(feature = "typestate")]
+pub use secret_n::{SecretCounter, SecretN};
+#[cfg(all(feature = "mlock", unix))]
+pub use hardened::HardenedBytes;
+#[cfg(feature = "memory-encryption")]
+pub use encrypted::{ChaCha20Poly1305Backend, CipherBackend, EncryptedSecret};
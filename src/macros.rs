@@ -0,0 +1,75 @@
+//! Macro for stamping out new secret wrapper types
+
+/// Define a new named wrapper type around [`Secret<T>`](crate::Secret)
+///
+/// Generates a struct that stores its value in a [`Secret`](crate::Secret), masks
+/// Debug/Display output as `TypeName(***)`/`***`, and exposes the value only through
+/// an `expose()` method. This gives two secrets built from the same underlying type
+/// (e.g. two `String`-backed secrets such as an API key and a signing seed) distinct,
+/// non-interchangeable Rust types, so the compiler catches accidental swaps at call
+/// sites instead of relying on the programmer to keep them straight.
+///
+/// Additional trait impls (`From`, `AsRef`, `PartialEq`, `FromStr`, ...) are left for
+/// the caller to add, since they're usually specific to the wrapped type; see
+/// [`SecretBytes`](crate::SecretBytes) and [`SecretString`](crate::SecretString) for
+/// examples built this way.
+///
+/// # Example
+///
+/// ```
+/// use saferet::define_secret;
+///
+/// define_secret! {
+///     /// A signing key for outbound webhooks
+///     pub struct ApiKey(String) as str;
+/// }
+///
+/// let key = ApiKey::new("sk_live_abc123");
+/// assert_eq!(format!("{:?}", key), "ApiKey(***)");
+/// assert_eq!(key.expose(), "sk_live_abc123");
+/// ```
+#[macro_export]
+macro_rules! define_secret {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident($inner:ty) as $borrowed:ty;
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone)]
+        $vis struct $name($crate::Secret<$inner>);
+
+        impl $name {
+            #[doc = concat!("Create a new `", stringify!($name), "`")]
+            pub fn new(secret: impl Into<$inner>) -> Self {
+                Self($crate::Secret::new(secret.into()))
+            }
+
+            /// Get a reference to the internal value
+            ///
+            /// # Security Warning
+            ///
+            /// Do not output this value to logs or include it in error messages.
+            /// Use this method carefully and only when necessary.
+            pub fn expose(&self) -> &$borrowed {
+                self.0.expose()
+            }
+
+            /// Explicitly zero out the internal value
+            pub fn zeroize(&mut self) {
+                self.0.zeroize();
+            }
+        }
+
+        impl ::core::fmt::Debug for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                write!(f, concat!(stringify!($name), "(***)"))
+            }
+        }
+
+        impl ::core::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                write!(f, "***")
+            }
+        }
+    };
+}
@@ -0,0 +1,117 @@
+//! Generic secret container
+//!
+//! Provides [`Secret<T>`] for securely holding any [`Zeroize`] value. [`SecretBytes`](crate::SecretBytes)
+//! and [`SecretString`](crate::SecretString) are built on top of it; use
+//! [`define_secret!`](crate::define_secret) to wrap other `Zeroize` types (keypairs,
+//! scalars, structs, ...) the same way without reimplementing the Drop/Debug/Display
+//! plumbing.
+
+use alloc::boxed::Box;
+use core::fmt;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+/// Generic container for a value that must be protected in memory
+///
+/// Holds `T` in a heap allocation, zeroizes it on Drop, and exposes it only through
+/// [`expose`](Secret::expose)/[`expose_mut`](Secret::expose_mut).
+pub struct Secret<T: Zeroize>(Box<T>);
+
+impl<T: Zeroize> Secret<T> {
+    /// Create a new `Secret` wrapping `value`
+    pub fn new(value: T) -> Self {
+        Self(Box::new(value))
+    }
+
+    /// Get a reference to the internal value
+    ///
+    /// # Security Warning
+    ///
+    /// Do not output this value to logs or include it in error messages.
+    /// Use this method carefully and only when necessary.
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+
+    /// Get a mutable reference to the internal value
+    ///
+    /// # Security Warning
+    ///
+    /// Do not output this value to logs or include it in error messages.
+    /// Use this method carefully and only when necessary.
+    pub fn expose_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+
+    /// Explicitly zero out the internal value
+    pub fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<T: Zeroize + Clone> Clone for Secret<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: Zeroize> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<T: Zeroize> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secret(***)")
+    }
+}
+
+impl<T: Zeroize> fmt::Display for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "***")
+    }
+}
+
+/// Plain (non constant-time) equality, available for any `Zeroize + PartialEq` value.
+///
+/// Types that need timing-safe comparison (e.g. [`SecretBytes`](crate::SecretBytes),
+/// [`SecretString`](crate::SecretString)) should implement `PartialEq` themselves
+/// instead of relying on this impl.
+impl<T: Zeroize + PartialEq> PartialEq for Secret<T> {
+    fn eq(&self, other: &Self) -> bool {
+        *self.0 == *other.0
+    }
+}
+
+impl<T: Zeroize + Eq> Eq for Secret<T> {}
+
+/// Refuses to serialize by default; see the crate's `serialize-secrets` feature.
+///
+/// `T` is arbitrary here, so there's no generically sensible masked representation to
+/// fall back to (unlike [`SecretBytes`](crate::SecretBytes)/[`SecretString`](crate::SecretString),
+/// which mask as `"***"`). Enable `serialize-secrets` to serialize `T` as-is for the
+/// rare case where a secret must be written to an already-encrypted sink.
+#[cfg(feature = "serde")]
+impl<T: Zeroize + Serialize> Serialize for Secret<T> {
+    #[cfg(feature = "serialize-secrets")]
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+
+    #[cfg(not(feature = "serialize-secrets"))]
+    fn serialize<S: serde::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom(
+            "saferet: refusing to serialize a Secret value; enable the `serialize-secrets` \
+             feature to allow it",
+        ))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Zeroize + Deserialize<'de>> Deserialize<'de> for Secret<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self::new(T::deserialize(deserializer)?))
+    }
+}
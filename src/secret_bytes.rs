@@ -28,51 +28,33 @@
 //! - Vector reallocation may leave copies at old memory locations
 //! - With `constant-time-eq` feature (enabled by default), comparison operations use
 //!   constant-time algorithms to prevent timing attacks
+//! - `SecretBytes` deliberately does not implement `Ord`, `PartialOrd`, or `Hash`, since
+//!   either would leak timing or bucketing information about the secret; use
+//!   [`secure_cmp`](SecretBytes::secure_cmp) if you genuinely need to order secrets
+//! - With the `serde` feature, `Serialize` refuses to emit the cleartext by default
+//!   (masking as `"***"` for human-readable formats, erroring otherwise); enable
+//!   `serialize-secrets` to opt into writing the real bytes
+//!
+//! `SecretBytes` is built on top of the generic [`Secret<T>`](crate::Secret) container via
+//! [`define_secret!`](crate::define_secret); use that macro directly to wrap other
+//! `Zeroize` types the same way.
 //!
 //! [`zeroize`]: https://docs.rs/zeroize
 
+#[cfg(feature = "rand")]
+use alloc::vec;
+use alloc::vec::Vec;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::fmt;
 #[cfg(feature = "constant-time-eq")]
 use subtle::ConstantTimeEq;
-use zeroize::{Zeroize, ZeroizeOnDrop};
-
-/// Byte vector containing sensitive information
-///
-/// Automatically cleaned from memory on Drop, and masked in Debug/Display output.
-#[derive(Clone, Zeroize, ZeroizeOnDrop)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[cfg_attr(not(feature = "constant-time-eq"), derive(PartialEq, Eq))]
-pub struct SecretBytes(Vec<u8>);
-
-impl SecretBytes {
-    /// Create a new `SecretBytes`
-    pub fn new(secret: impl Into<Vec<u8>>) -> Self {
-        Self(secret.into())
-    }
 
-    /// Get a reference to the internal byte slice
-    ///
-    /// # Security Warning
+crate::define_secret! {
+    /// Byte vector containing sensitive information
     ///
-    /// Do not output this value to logs or include it in error messages.
-    /// Use this method carefully and only when necessary.
-    pub fn expose(&self) -> &[u8] {
-        &self.0
-    }
-}
-
-impl fmt::Debug for SecretBytes {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "SecretBytes(***)")
-    }
-}
-
-impl fmt::Display for SecretBytes {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "***")
-    }
+    /// Automatically cleaned from memory on Drop, and masked in Debug/Display output.
+    #[cfg_attr(not(feature = "constant-time-eq"), derive(PartialEq, Eq))]
+    pub struct SecretBytes(Vec<u8>) as [u8];
 }
 
 impl From<Vec<u8>> for SecretBytes {
@@ -99,16 +81,132 @@ impl Default for SecretBytes {
     }
 }
 
+#[cfg(feature = "rand")]
+impl SecretBytes {
+    /// Generate `len` cryptographically secure random bytes directly into a `SecretBytes`
+    ///
+    /// Fills the buffer with [`rand::rngs::OsRng`] and the buffer becomes the secret's
+    /// own storage with no extra copy, avoiding the common footgun of generating key
+    /// material into a separate plain buffer and then wrapping a copy of it.
+    pub fn random(len: usize) -> Self {
+        Self::random_with(rand::rngs::OsRng, len)
+    }
+
+    /// Like [`random`](SecretBytes::random), but with a caller-supplied RNG
+    pub fn random_with<R: rand::CryptoRng + rand::RngCore>(mut rng: R, len: usize) -> Self {
+        let mut bytes = vec![0u8; len];
+        rng.fill_bytes(&mut bytes);
+        Self::new(bytes)
+    }
+}
+
 #[cfg(feature = "constant-time-eq")]
 impl PartialEq for SecretBytes {
     fn eq(&self, other: &Self) -> bool {
-        self.0.ct_eq(&other.0).into()
+        self.expose().ct_eq(other.expose()).into()
     }
 }
 
 #[cfg(feature = "constant-time-eq")]
 impl Eq for SecretBytes {}
 
+/// Refuses to serialize the cleartext by default: masks as `"***"` for human-readable
+/// formats (e.g. JSON), errors otherwise. Enable the `serialize-secrets` feature for
+/// the rare case where a secret must be written to an already-encrypted sink, in which
+/// case binary/non-human-readable formats get the bytes as an efficient byte sequence
+/// rather than a sequence of individually-encoded integers.
+#[cfg(feature = "serde")]
+impl Serialize for SecretBytes {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[cfg(not(feature = "serialize-secrets"))]
+        {
+            if serializer.is_human_readable() {
+                return serializer.serialize_str("***");
+            }
+            return Err(serde::ser::Error::custom(
+                "saferet: refusing to serialize SecretBytes; enable the `serialize-secrets` \
+                 feature to allow it",
+            ));
+        }
+        #[cfg(feature = "serialize-secrets")]
+        {
+            serializer.serialize_bytes(self.expose())
+        }
+    }
+}
+
+/// Deserializes straight into a `SecretBytes`. An owned buffer handed over by the
+/// deserializer becomes the secret's own zeroize-on-drop storage without a copy;
+/// borrowed bytes and element sequences are first collected into a new buffer.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for SecretBytes {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct BytesVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for BytesVisitor {
+            type Value = SecretBytes;
+
+            fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.write_str("a byte sequence")
+            }
+
+            fn visit_byte_buf<E: serde::de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+                Ok(SecretBytes::new(v))
+            }
+
+            fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(SecretBytes::new(v.to_vec()))
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<Self::Value, A::Error> {
+                let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                }
+                Ok(SecretBytes::new(bytes))
+            }
+        }
+
+        deserializer.deserialize_bytes(BytesVisitor)
+    }
+}
+
+impl SecretBytes {
+    /// Compare two secrets in constant time with respect to content
+    ///
+    /// Orders by length first (two secrets of differing length compare by length,
+    /// without looking at content), then, for equal-length secrets, by the first
+    /// differing byte. Every byte of an equal-length pair is folded into the result
+    /// without an early return, so the running time does not depend on where (or
+    /// whether) they first differ. This is **not** the same ordering as a plain
+    /// lexicographic byte comparison: e.g. `b"z"` sorts before `b"aa"` here because it
+    /// is shorter, whereas lexicographic order would put `b"aa"` first.
+    ///
+    /// `SecretBytes` deliberately does not implement `Ord`/`PartialOrd`, so reach for
+    /// this method instead of `expose()`-ing both sides and comparing raw bytes.
+    pub fn secure_cmp(&self, other: &Self) -> core::cmp::Ordering {
+        let a = self.expose();
+        let b = other.expose();
+
+        if a.len() != b.len() {
+            return a.len().cmp(&b.len());
+        }
+
+        let mut ordering: i8 = 0;
+        for (&x, &y) in a.iter().zip(b.iter()) {
+            let undecided = (ordering == 0) as i8;
+            let gt = ((x > y) as i8) * undecided;
+            let lt = ((x < y) as i8) * undecided;
+            ordering += gt - lt;
+        }
+
+        ordering.cmp(&0)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -201,4 +299,58 @@ mod tests {
         let slice: &[u8] = secret.as_ref();
         assert_eq!(slice, &[0x01, 0x02, 0x03]);
     }
+
+    /// Verify that secure_cmp compares equal-length secrets byte-by-byte, and orders
+    /// differing lengths by length first (not a plain lexicographic comparison)
+    #[test]
+    fn test_secure_cmp() {
+        use std::cmp::Ordering;
+
+        let a = SecretBytes::new(vec![0x01, 0x02]);
+        let b = SecretBytes::new(vec![0x01, 0x03]);
+        let c = SecretBytes::new(vec![0x01, 0x02]);
+        let shorter = SecretBytes::new(vec![0x01]);
+
+        assert_eq!(a.secure_cmp(&b), Ordering::Less);
+        assert_eq!(b.secure_cmp(&a), Ordering::Greater);
+        assert_eq!(a.secure_cmp(&c), Ordering::Equal);
+        assert_eq!(shorter.secure_cmp(&a), Ordering::Less);
+    }
+
+    /// Verify that secure_cmp orders by length first, unlike a lexicographic comparison
+    #[test]
+    fn test_secure_cmp_length_before_lexicographic() {
+        use std::cmp::Ordering;
+
+        let short = SecretBytes::new(vec![0xFF]);
+        let long = SecretBytes::new(vec![0x00, 0x00]);
+
+        assert_eq!(short.secure_cmp(&long), Ordering::Less);
+        assert_eq!([0xFFu8].as_slice().cmp([0x00u8, 0x00].as_slice()), Ordering::Greater);
+    }
+
+    /// Verify that random() generates bytes of the requested length
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_random() {
+        let secret = SecretBytes::random(16);
+        assert_eq!(secret.expose().len(), 16);
+    }
+
+    /// Verify that random_with() draws from the supplied RNG
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_random_with() {
+        let secret = SecretBytes::random_with(rand::rngs::OsRng, 8);
+        assert_eq!(secret.expose().len(), 8);
+    }
+
+    /// Verify that Serialize masks the cleartext by default
+    #[cfg(all(feature = "serde", not(feature = "serialize-secrets")))]
+    #[test]
+    fn test_serialize_masks_by_default() {
+        let secret = SecretBytes::new(vec![0x01, 0x02, 0x03]);
+        let json = serde_json::to_string(&secret).unwrap();
+        assert_eq!(json, "\"***\"");
+    }
 }
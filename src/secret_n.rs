@@ -0,0 +1,160 @@
+//! Compile-time–bounded exposure counting
+//!
+//! Provides [`SecretN<T, MEC, EC>`] for secrets that must never be read more than a
+//! fixed number of times (e.g. a one-time signing key), enforced by the type system
+//! with zero runtime cost, and [`SecretCounter<T>`] as a runtime-checked fallback for
+//! callers who don't want to thread exposure counts through their types.
+//!
+//! Requires the `typestate` feature.
+
+use std::marker::PhantomData;
+use typenum::{Add1, IsLessOrEqual, True, Unsigned, B1};
+use zeroize::Zeroize;
+
+use crate::Secret;
+
+/// A secret whose exposure count is tracked in its type, statically limited to `MEC`
+/// (maximum exposure count) exposures.
+///
+/// Each call to [`expose`](SecretN::expose) consumes `self` and returns a new
+/// `SecretN` whose `EC` (current exposure count) type parameter is incremented by
+/// one. The `where` bound on `expose` makes the program fail to compile once `EC`
+/// would exceed `MEC`, catching over-reads of key material at compile time instead of
+/// at runtime. The counter lives purely in `EC`; there is no runtime counter field.
+///
+/// # Example
+///
+/// ```ignore
+/// use saferet::SecretN;
+/// use typenum::U1;
+///
+/// let secret: SecretN<Vec<u8>, U1, typenum::U0> = SecretN::new(vec![0x01, 0x02]);
+/// let (secret, len) = secret.expose(|bytes| bytes.len());
+/// assert_eq!(len, 2);
+/// // A second `secret.expose(..)` here would fail to compile: EC (1) + 1 > MEC (1).
+/// ```
+pub struct SecretN<T: Zeroize, MEC: Unsigned, EC: Unsigned> {
+    inner: Secret<T>,
+    _mec: PhantomData<MEC>,
+    _ec: PhantomData<EC>,
+}
+
+impl<T: Zeroize, MEC: Unsigned> SecretN<T, MEC, typenum::U0> {
+    /// Create a new `SecretN` with its exposure count starting at zero
+    pub fn new(value: T) -> Self {
+        Self {
+            inner: Secret::new(value),
+            _mec: PhantomData,
+            _ec: PhantomData,
+        }
+    }
+}
+
+impl<T: Zeroize, MEC: Unsigned, EC: Unsigned> SecretN<T, MEC, EC> {
+    /// Expose the secret to `f`, returning `f`'s result alongside a new `SecretN`
+    /// whose exposure count is incremented by one.
+    ///
+    /// Fails to compile if this exposure would exceed `MEC`.
+    pub fn expose<F, R>(self, f: F) -> (SecretN<T, MEC, Add1<EC>>, R)
+    where
+        F: FnOnce(&T) -> R,
+        EC: std::ops::Add<B1>,
+        Add1<EC>: Unsigned + IsLessOrEqual<MEC, Output = True>,
+    {
+        let result = f(self.inner.expose());
+        (
+            SecretN {
+                inner: self.inner,
+                _mec: PhantomData,
+                _ec: PhantomData,
+            },
+            result,
+        )
+    }
+
+    /// Number of times this secret has been exposed so far
+    pub fn exposure_count() -> usize {
+        EC::to_usize()
+    }
+
+    /// Maximum number of times this secret may ever be exposed
+    pub fn max_exposures() -> usize {
+        MEC::to_usize()
+    }
+}
+
+/// Runtime-checked equivalent of [`SecretN`] for callers who don't want to thread
+/// exposure counts through their types.
+///
+/// Exceeding `max_exposures` panics rather than failing to compile; prefer
+/// [`SecretN`] when the limit is known at compile time.
+pub struct SecretCounter<T: Zeroize> {
+    inner: Secret<T>,
+    max_exposures: usize,
+    exposure_count: usize,
+}
+
+impl<T: Zeroize> SecretCounter<T> {
+    /// Create a new `SecretCounter` allowing at most `max_exposures` exposures
+    pub fn new(value: T, max_exposures: usize) -> Self {
+        Self {
+            inner: Secret::new(value),
+            max_exposures,
+            exposure_count: 0,
+        }
+    }
+
+    /// Expose the secret to `f`
+    ///
+    /// # Panics
+    ///
+    /// Panics if this would exceed `max_exposures`.
+    pub fn expose<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        assert!(
+            self.exposure_count < self.max_exposures,
+            "SecretCounter: exposure limit ({}) exceeded",
+            self.max_exposures
+        );
+        self.exposure_count += 1;
+        f(self.inner.expose())
+    }
+
+    /// Number of times this secret has been exposed so far
+    pub fn exposure_count(&self) -> usize {
+        self.exposure_count
+    }
+
+    /// Maximum number of times this secret may ever be exposed
+    pub fn max_exposures(&self) -> usize {
+        self.max_exposures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that the runtime-counting fallback allows exposures up to the limit
+    #[test]
+    fn test_secret_counter_allows_up_to_limit() {
+        let mut secret = SecretCounter::new(vec![0x01, 0x02, 0x03], 2);
+
+        assert_eq!(secret.expose(|b| b.len()), 3);
+        assert_eq!(secret.expose(|b| b.len()), 3);
+        assert_eq!(secret.exposure_count(), 2);
+        assert_eq!(secret.max_exposures(), 2);
+    }
+
+    /// Verify that the runtime-counting fallback panics once the limit is exceeded
+    #[test]
+    #[should_panic(expected = "exposure limit")]
+    fn test_secret_counter_panics_past_limit() {
+        let mut secret = SecretCounter::new(vec![0x01], 1);
+
+        secret.expose(|b| b.len());
+        secret.expose(|b| b.len());
+    }
+}
@@ -28,52 +28,30 @@
 //! - String reallocation may leave copies at old memory locations
 //! - With `constant-time-eq` feature (enabled by default), comparison operations use
 //!   constant-time algorithms to prevent timing attacks
+//! - `SecretString` deliberately does not implement `Ord`, `PartialOrd`, or `Hash`, since
+//!   either would leak timing or bucketing information about the secret; use
+//!   [`secure_cmp`](SecretString::secure_cmp) if you genuinely need to order secrets
+//! - With the `serde` feature, `Serialize` refuses to emit the cleartext by default
+//!   (masking as `"***"`); enable `serialize-secrets` to opt into writing the real string
+//!
+//! `SecretString` is built on top of the generic [`Secret<T>`](crate::Secret) container via
+//! [`define_secret!`](crate::define_secret); use that macro directly to wrap other
+//! `Zeroize` types the same way.
 //!
 //! [`zeroize`]: https://docs.rs/zeroize
 
+use alloc::string::String;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::fmt;
-use std::str::FromStr;
 #[cfg(feature = "constant-time-eq")]
 use subtle::ConstantTimeEq;
-use zeroize::{Zeroize, ZeroizeOnDrop};
-
-/// String containing sensitive information
-///
-/// Automatically cleaned from memory on Drop, and masked in Debug/Display output.
-#[derive(Clone, Zeroize, ZeroizeOnDrop)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[cfg_attr(not(feature = "constant-time-eq"), derive(PartialEq, Eq))]
-pub struct SecretString(String);
-
-impl SecretString {
-    /// Create a new `SecretString`
-    pub fn new(secret: impl Into<String>) -> Self {
-        Self(secret.into())
-    }
 
-    /// Get a reference to the internal string
-    ///
-    /// # Security Warning
+crate::define_secret! {
+    /// String containing sensitive information
     ///
-    /// Do not output this value to logs or include it in error messages.
-    /// Use this method carefully and only when necessary.
-    pub fn expose(&self) -> &str {
-        &self.0
-    }
-}
-
-impl fmt::Debug for SecretString {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "SecretString(***)")
-    }
-}
-
-impl fmt::Display for SecretString {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "***")
-    }
+    /// Automatically cleaned from memory on Drop, and masked in Debug/Display output.
+    #[cfg_attr(not(feature = "constant-time-eq"), derive(PartialEq, Eq))]
+    pub struct SecretString(String) as str;
 }
 
 impl From<String> for SecretString {
@@ -100,7 +78,36 @@ impl Default for SecretString {
     }
 }
 
-impl FromStr for SecretString {
+#[cfg(feature = "rand")]
+impl SecretString {
+    /// Generate a `len`-character alphanumeric string directly into a `SecretString`
+    ///
+    /// Builds the string with [`rand::rngs::OsRng`] and the buffer becomes the
+    /// secret's own storage with no extra copy, avoiding the common footgun of
+    /// generating a token into a separate plain buffer and then wrapping a copy of it.
+    pub fn random_alphanumeric(len: usize) -> Self {
+        Self::random_alphanumeric_with(rand::rngs::OsRng, len)
+    }
+
+    /// Like [`random_alphanumeric`](SecretString::random_alphanumeric), but with a
+    /// caller-supplied RNG
+    pub fn random_alphanumeric_with<R: rand::CryptoRng + rand::RngCore>(
+        mut rng: R,
+        len: usize,
+    ) -> Self {
+        use rand::distributions::{Alphanumeric, Distribution};
+
+        let s: String = (0..len)
+            .map(|_| Alphanumeric.sample(&mut rng) as char)
+            .collect();
+        Self::new(s)
+    }
+}
+
+/// `FromStr`'s `Infallible` error type lives in `std`, so this impl is only available
+/// with the `std` feature; `no_std` callers can use [`SecretString::new`] directly.
+#[cfg(feature = "std")]
+impl std::str::FromStr for SecretString {
     type Err = std::convert::Infallible;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -111,13 +118,72 @@ impl FromStr for SecretString {
 #[cfg(feature = "constant-time-eq")]
 impl PartialEq for SecretString {
     fn eq(&self, other: &Self) -> bool {
-        self.0.as_bytes().ct_eq(other.0.as_bytes()).into()
+        self.expose().as_bytes().ct_eq(other.expose().as_bytes()).into()
     }
 }
 
 #[cfg(feature = "constant-time-eq")]
 impl Eq for SecretString {}
 
+/// Refuses to serialize the cleartext by default, masking as `"***"`. Enable the
+/// `serialize-secrets` feature for the rare case where a secret must be written to an
+/// already-encrypted sink.
+#[cfg(feature = "serde")]
+impl Serialize for SecretString {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[cfg(not(feature = "serialize-secrets"))]
+        {
+            serializer.serialize_str("***")
+        }
+        #[cfg(feature = "serialize-secrets")]
+        {
+            serializer.serialize_str(self.expose())
+        }
+    }
+}
+
+/// Deserializes straight into a `SecretString`; the incoming `String` becomes the
+/// secret's own zeroize-on-drop storage rather than being copied into it.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for SecretString {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(SecretString::new(String::deserialize(deserializer)?))
+    }
+}
+
+impl SecretString {
+    /// Compare two secrets in constant time with respect to content
+    ///
+    /// Orders by length first (two secrets of differing length compare by length,
+    /// without looking at content), then, for equal-length secrets, by the first
+    /// differing byte. Every byte of an equal-length pair is folded into the result
+    /// without an early return, so the running time does not depend on where (or
+    /// whether) they first differ. This is **not** the same ordering as a plain
+    /// lexicographic comparison: e.g. `"z"` sorts before `"aa"` here because it is
+    /// shorter, whereas lexicographic order would put `"aa"` first.
+    ///
+    /// `SecretString` deliberately does not implement `Ord`/`PartialOrd`, so reach for
+    /// this method instead of `expose()`-ing both sides and comparing raw bytes.
+    pub fn secure_cmp(&self, other: &Self) -> core::cmp::Ordering {
+        let a = self.expose().as_bytes();
+        let b = other.expose().as_bytes();
+
+        if a.len() != b.len() {
+            return a.len().cmp(&b.len());
+        }
+
+        let mut ordering: i8 = 0;
+        for (&x, &y) in a.iter().zip(b.iter()) {
+            let undecided = (ordering == 0) as i8;
+            let gt = ((x > y) as i8) * undecided;
+            let lt = ((x < y) as i8) * undecided;
+            ordering += gt - lt;
+        }
+
+        ordering.cmp(&0)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -204,8 +270,11 @@ mod tests {
     }
 
     /// Verify FromStr trait implementation
+    #[cfg(feature = "std")]
     #[test]
     fn test_from_str() {
+        use std::str::FromStr;
+
         // Using parse() method
         let secret: SecretString = "parsed_secret".parse().unwrap();
         assert_eq!(secret.expose(), "parsed_secret");
@@ -214,4 +283,59 @@ mod tests {
         let secret2 = SecretString::from_str("direct_from_str").unwrap();
         assert_eq!(secret2.expose(), "direct_from_str");
     }
+
+    /// Verify that secure_cmp compares equal-length secrets byte-by-byte, and orders
+    /// differing lengths by length first (not a plain lexicographic comparison)
+    #[test]
+    fn test_secure_cmp() {
+        use std::cmp::Ordering;
+
+        let a = SecretString::new("abc");
+        let b = SecretString::new("abd");
+        let c = SecretString::new("abc");
+        let shorter = SecretString::new("ab");
+
+        assert_eq!(a.secure_cmp(&b), Ordering::Less);
+        assert_eq!(b.secure_cmp(&a), Ordering::Greater);
+        assert_eq!(a.secure_cmp(&c), Ordering::Equal);
+        assert_eq!(shorter.secure_cmp(&a), Ordering::Less);
+    }
+
+    /// Verify that secure_cmp orders by length first, unlike a lexicographic comparison
+    #[test]
+    fn test_secure_cmp_length_before_lexicographic() {
+        use std::cmp::Ordering;
+
+        let z = SecretString::new("z");
+        let aa = SecretString::new("aa");
+
+        assert_eq!(z.secure_cmp(&aa), Ordering::Less);
+        assert_eq!("z".cmp("aa"), Ordering::Greater);
+    }
+
+    /// Verify that random_alphanumeric() generates a string of the requested length
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_random_alphanumeric() {
+        let secret = SecretString::random_alphanumeric(16);
+        assert_eq!(secret.expose().len(), 16);
+        assert!(secret.expose().chars().all(|c| c.is_ascii_alphanumeric()));
+    }
+
+    /// Verify that random_alphanumeric_with() draws from the supplied RNG
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_random_alphanumeric_with() {
+        let secret = SecretString::random_alphanumeric_with(rand::rngs::OsRng, 8);
+        assert_eq!(secret.expose().len(), 8);
+    }
+
+    /// Verify that Serialize masks the cleartext by default
+    #[cfg(all(feature = "serde", not(feature = "serialize-secrets")))]
+    #[test]
+    fn test_serialize_masks_by_default() {
+        let secret = SecretString::new("super-secret");
+        let json = serde_json::to_string(&secret).unwrap();
+        assert_eq!(json, "\"***\"");
+    }
 }